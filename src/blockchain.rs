@@ -0,0 +1,232 @@
+// Copyright 2018 OpenST Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Connects to and observes an Ethereum-compatible blockchain.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use web3::api::Namespace;
+use web3::futures::Future;
+use web3::helpers::CallFuture;
+use web3::transports::Http;
+use web3::types::{BlockId, BlockNumber, H256, U256};
+use web3::{Transport, Web3};
+
+/// A connection to a single Ethereum-compatible node.
+pub struct EthereumNode {
+    web3: Web3<Http>,
+}
+
+/// Connects to an Ethereum-compatible node at `address`.
+pub fn connect_to_ethereum(address: String) -> EthereumNode {
+    let (eloop, transport) = Http::new(&address).expect("could not create http transport");
+    // The event loop handle has to outlive the transport it drives; since a mosaic node runs for
+    // the lifetime of the process, we intentionally leak it rather than threading it through.
+    eloop.into_remote();
+
+    EthereumNode {
+        web3: Web3::new(transport),
+    }
+}
+
+impl EthereumNode {
+    /// Returns a handle to the underlying web3 connection, e.g. to hand off to a
+    /// [`ChainObserver`].
+    pub(crate) fn web3(&self) -> Web3<Http> {
+        self.web3.clone()
+    }
+
+    /// Returns the chain id the node reports via `eth_chainId`, as a decimal string suitable for
+    /// comparison against a CAIP-2 reference segment.
+    ///
+    /// `web3` has no typed `eth_chainId` method, so this issues the raw RPC call directly.
+    pub fn chain_id(&self) -> Result<String, String> {
+        let eth = self.web3.eth();
+        let call: CallFuture<U256, _> = CallFuture::new(eth.transport().execute("eth_chainId", vec![]));
+        call.wait()
+            .map(|id| id.to_string())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// A block that has reached the configured confirmation depth on its chain.
+#[derive(Debug, Clone)]
+pub struct FinalizedBlock {
+    pub number: u64,
+    pub hash: H256,
+    pub parent_hash: H256,
+}
+
+/// How many already-emitted blocks `ChainObserver` keeps on hand to detect and reconcile reorgs
+/// against. A reorg that reaches past the oldest remembered block is not checked any further back:
+/// the live chain is trusted from that point on.
+const MAX_REORG_DEPTH: usize = 64;
+
+/// Polls a single chain for newly finalized blocks and emits them to a consumer via a channel.
+///
+/// A block counts as finalized once it is `confirmations` blocks behind the chain tip. Between
+/// polls, `ChainObserver` detects reorgs by checking that the parent hash of the next block
+/// still matches the hash of the last block it emitted; on a mismatch it walks backward,
+/// re-fetching blocks and discarding its own stale history until the live chain's ancestor hash
+/// matches a block it still remembers, before resuming forward from that common ancestor.
+pub struct ChainObserver {
+    web3: Web3<Http>,
+    poll_interval: Duration,
+    confirmations: u64,
+    /// The most recently emitted blocks, oldest first, used to detect and reconcile reorgs.
+    history: VecDeque<FinalizedBlock>,
+    responsive: Arc<AtomicBool>,
+}
+
+impl ChainObserver {
+    /// Creates a new observer for the chain `node` is connected to. `responsive` is flipped to
+    /// `false` whenever a poll fails and back to `true` once polling succeeds again, so it can be
+    /// shared with e.g. the `mosaic_health` RPC method.
+    pub fn new(
+        node: &EthereumNode,
+        poll_interval: Duration,
+        confirmations: u64,
+        responsive: Arc<AtomicBool>,
+    ) -> ChainObserver {
+        ChainObserver {
+            web3: node.web3(),
+            poll_interval,
+            confirmations,
+            history: VecDeque::new(),
+            responsive,
+        }
+    }
+
+    /// Spawns a background thread that polls for newly finalized blocks at `poll_interval` and
+    /// sends each one on the returned channel.
+    pub fn spawn(mut self) -> Receiver<FinalizedBlock> {
+        let (sender, receiver) = channel();
+
+        thread::spawn(move || loop {
+            let result = self.poll_once(&sender);
+            self.responsive.store(result.is_ok(), Ordering::SeqCst);
+            if let Err(err) = result {
+                warn!("could not poll chain for new blocks: {}", err);
+            }
+            thread::sleep(self.poll_interval);
+        });
+
+        receiver
+    }
+
+    /// Fetches any newly finalized blocks since the last poll and sends them to `sender`,
+    /// reconciling a reorg first if one is detected.
+    fn poll_once(&mut self, sender: &Sender<FinalizedBlock>) -> Result<(), String> {
+        let head = self
+            .web3
+            .eth()
+            .block_number()
+            .wait()
+            .map_err(|err| err.to_string())?;
+        let finalized_number = head.as_u64().saturating_sub(self.confirmations);
+
+        let mut number = match self.history.back() {
+            Some(last) => last.number + 1,
+            None => finalized_number,
+        };
+
+        while number <= finalized_number {
+            let block = self.fetch_block(number)?;
+
+            let is_continuous = match self.history.back() {
+                Some(last) => block.parent_hash == last.hash,
+                None => true,
+            };
+
+            if !is_continuous {
+                warn!(
+                    "reorg detected: block {} no longer has our recorded block as its parent; \
+                     walking back to find a common ancestor",
+                    number
+                );
+                number = self.reconcile_reorg()?;
+                continue;
+            }
+
+            info!("new finalized block {} ({:?})", block.number, block.hash);
+            self.remember(block.clone());
+            sender.send(block).map_err(|err| err.to_string())?;
+            number += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Walks the live chain backward from our most recently remembered block, discarding stale
+    /// history entries, until a live ancestor's hash matches the block we still have on record at
+    /// that height (or our history is exhausted, in which case the live chain is trusted from
+    /// there). Returns the block number to resume forward polling from.
+    fn reconcile_reorg(&mut self) -> Result<u64, String> {
+        loop {
+            let stale = self
+                .history
+                .pop_back()
+                .ok_or_else(|| "reorg deeper than the observer's history window, cannot reconcile".to_string())?;
+
+            if stale.number == 0 {
+                return Err("reorg reaches genesis, cannot reconcile".to_string());
+            }
+
+            let live_ancestor = self.fetch_block(stale.number - 1)?;
+            match self.history.back() {
+                // `previous` is already that same block, remembered from before the reorg; it
+                // stays in history as-is rather than being pushed again as a duplicate.
+                Some(previous) if previous.hash == live_ancestor.hash => {
+                    return Ok(previous.number + 1);
+                }
+                None => {
+                    let resume_from = live_ancestor.number + 1;
+                    self.history.push_back(live_ancestor);
+                    return Ok(resume_from);
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    /// Records `block` as the most recently emitted one, bounding how far back a reorg can later
+    /// be reconciled.
+    fn remember(&mut self, block: FinalizedBlock) {
+        self.history.push_back(block);
+        while self.history.len() > MAX_REORG_DEPTH {
+            self.history.pop_front();
+        }
+    }
+
+    fn fetch_block(&self, number: u64) -> Result<FinalizedBlock, String> {
+        let block = self
+            .web3
+            .eth()
+            .block(BlockId::Number(BlockNumber::Number(number)))
+            .wait()
+            .map_err(|err| err.to_string())?
+            .ok_or_else(|| format!("block {} not found", number))?;
+
+        Ok(FinalizedBlock {
+            number: block.number.map(|n| n.as_u64()).unwrap_or(number),
+            hash: block.hash.unwrap_or_default(),
+            parent_hash: block.parent_hash,
+        })
+    }
+}
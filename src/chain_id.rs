@@ -0,0 +1,126 @@
+// Copyright 2018 OpenST Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! CAIP-2 chain identifiers (`namespace:reference`, e.g. `eip155:1`).
+//!
+//! A mosaic node commits value between an origin and an auxiliary chain, so pointing a config at
+//! the wrong endpoint is dangerous. Declaring the expected chain id in the config lets the node
+//! cross-check it against the live `eth_chainId` of the node it connects to and refuse to run on
+//! a mismatch, rather than silently committing against the wrong chain.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+
+/// Maximum length of a CAIP-2 namespace segment.
+const MAX_NAMESPACE_LEN: usize = 8;
+/// Maximum length of a CAIP-2 reference segment.
+const MAX_REFERENCE_LEN: usize = 32;
+
+/// A CAIP-2 chain identifier, e.g. `eip155:1` for Ethereum mainnet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainId {
+    /// The namespace, identifying the class of chain (e.g. `eip155` for Ethereum-based chains).
+    pub(crate) namespace: String,
+    /// The chain reference within the namespace (e.g. the EIP-155 chain id).
+    pub(crate) reference: String,
+}
+
+impl FromStr for ChainId {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut segments = value.splitn(2, ':');
+        let namespace = segments.next().unwrap_or("");
+        let reference = segments
+            .next()
+            .ok_or_else(|| format!("chain id '{}' is not in 'namespace:reference' form", value))?;
+
+        if namespace.is_empty()
+            || namespace.len() > MAX_NAMESPACE_LEN
+            || !namespace.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        {
+            return Err(format!(
+                "invalid CAIP-2 namespace '{}': must be 1-{} lowercase alphanumeric characters",
+                namespace, MAX_NAMESPACE_LEN
+            ));
+        }
+
+        if reference.is_empty()
+            || reference.len() > MAX_REFERENCE_LEN
+            || !reference.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(format!(
+                "invalid CAIP-2 reference '{}': must be 1-{} alphanumeric characters or hyphens",
+                reference, MAX_REFERENCE_LEN
+            ));
+        }
+
+        Ok(ChainId {
+            namespace: namespace.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.reference)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_a_valid_chain_id() {
+        let chain_id: ChainId = "eip155:1".parse().unwrap();
+        assert_eq!(chain_id.namespace, "eip155");
+        assert_eq!(chain_id.reference, "1");
+        assert_eq!(chain_id.to_string(), "eip155:1");
+    }
+
+    #[test]
+    fn it_rejects_a_chain_id_without_a_reference() {
+        assert!("eip155".parse::<ChainId>().is_err());
+    }
+
+    #[test]
+    fn it_rejects_an_overlong_namespace() {
+        assert!("toolongnamespace:1".parse::<ChainId>().is_err());
+    }
+
+    #[test]
+    fn it_rejects_a_non_alphanumeric_reference() {
+        assert!("eip155:not-alphanumeric!".parse::<ChainId>().is_err());
+    }
+
+    #[test]
+    fn it_accepts_a_hyphenated_reference() {
+        let chain_id: ChainId = "cosmos:cosmoshub-3".parse().unwrap();
+        assert_eq!(chain_id.reference, "cosmoshub-3");
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2018 OpenST Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Command-line interface for the mosaic node.
+//!
+//! CLI arguments take precedence over everything else: they override values already layered
+//! from defaults, the config file, and the environment (see [`crate::config`]).
+
+use clap::{App, Arg, ArgMatches};
+
+/// Parsed command-line arguments for a mosaic node.
+#[derive(Debug, Default, Clone)]
+pub struct Cli {
+    /// Overrides the origin chain address.
+    pub(crate) origin: Option<String>,
+    /// Overrides the auxiliary chain address.
+    pub(crate) auxiliary: Option<String>,
+    /// Path to a config file to load instead of the default `config.{yaml,toml,...}`.
+    pub(crate) config_path: Option<String>,
+    /// Overrides the environment (`development`/`production`).
+    pub(crate) environment: Option<String>,
+    /// Whether to daemonize the process instead of running in the foreground.
+    pub(crate) daemon: bool,
+    /// Enables the JSON-RPC status/health server.
+    pub(crate) rpc: bool,
+    /// Overrides the JSON-RPC server's bind address.
+    pub(crate) rpc_bind_address: Option<String>,
+}
+
+impl Cli {
+    /// Parses `Cli` from the process's command-line arguments.
+    pub fn from_args() -> Cli {
+        Cli::from_matches(&build_app().get_matches())
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Cli {
+        Cli {
+            origin: matches.value_of("origin").map(String::from),
+            auxiliary: matches.value_of("auxiliary").map(String::from),
+            config_path: matches.value_of("config").map(String::from),
+            environment: matches.value_of("env").map(String::from),
+            daemon: matches.is_present("daemon"),
+            rpc: matches.is_present("rpc"),
+            rpc_bind_address: matches.value_of("rpc-bind-address").map(String::from),
+        }
+    }
+}
+
+fn build_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("mosaic-node")
+        .about("Validates utility systems and commits a value chain onto a utility chain.")
+        .arg(
+            Arg::with_name("origin")
+                .long("origin")
+                .takes_value(true)
+                .help("Address of the origin chain, e.g. http://127.0.0.1:8545"),
+        )
+        .arg(
+            Arg::with_name("auxiliary")
+                .long("auxiliary")
+                .takes_value(true)
+                .help("Address of the auxiliary chain, e.g. http://127.0.0.1:8546"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .help("Path to a config file"),
+        )
+        .arg(
+            Arg::with_name("env")
+                .long("env")
+                .takes_value(true)
+                .help("Environment to run in (development/production)"),
+        )
+        .arg(
+            Arg::with_name("daemon")
+                .long("daemon")
+                .takes_value(false)
+                .help("Run as a background daemon"),
+        )
+        .arg(
+            Arg::with_name("rpc")
+                .long("rpc")
+                .takes_value(false)
+                .help("Enable the JSON-RPC status/health server"),
+        )
+        .arg(
+            Arg::with_name("rpc-bind-address")
+                .long("rpc-bind-address")
+                .takes_value(true)
+                .help("Address the JSON-RPC server binds to, e.g. 127.0.0.1:3030"),
+        )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_overrides_from_matches() {
+        let app = build_app();
+        let matches = app
+            .get_matches_from(vec![
+                "mosaic-node",
+                "--origin",
+                "http://10.0.0.1:8545",
+                "--daemon",
+            ]);
+        let cli = Cli::from_matches(&matches);
+
+        assert_eq!(cli.origin, Some("http://10.0.0.1:8545".to_string()));
+        assert_eq!(cli.auxiliary, None);
+        assert!(cli.daemon);
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright 2018 OpenST Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Configuration for a mosaic node.
+//!
+//! Configuration is assembled in layers, each layer overriding the one before it:
+//!  1. built-in defaults
+//!  2. a `config.yaml` / `config.toml` file in the working directory (optional)
+//!  3. environment variables, including a `.env` file loaded at startup (optional)
+//!
+//! A missing config file is not an error, since a node can run on defaults and env vars alone.
+//! A config file that fails to parse is, since it indicates a typo the operator should fix.
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use config_rs::{Config as RawConfig, File};
+use serde::de::{self, Deserialize, Deserializer};
+
+use chain_id::ChainId;
+use cli::Cli;
+
+/// Base name of the config file, without extension. `config-rs` tries the supported extensions
+/// (`.yaml`, `.toml`, `.json`, ...) in turn and uses whichever one is present.
+const CONFIG_FILE_STEM: &str = "config";
+
+const ENV_ORIGIN_ADDRESS: &str = "MOSAIC_ORIGIN_ADDRESS";
+const ENV_AUXILIARY_ADDRESS: &str = "MOSAIC_AUXILIARY_ADDRESS";
+const ENV_ENVIRONMENT: &str = "ENVIRONMENT";
+const ENV_MOSAIC_ENV: &str = "MOSAIC_ENV";
+
+const DEFAULT_ORIGIN_ADDRESS: &str = "http://127.0.0.1:8545";
+const DEFAULT_AUXILIARY_ADDRESS: &str = "http://127.0.0.1:8546";
+const DEFAULT_RPC_BIND_ADDRESS: &str = "127.0.0.1:3030";
+
+/// The environment a mosaic node is running in. Controls whether a declared chain id mismatch
+/// (see [`ChainConfig::chain_id`]) is a hard error or just a warning: a `Production` node refuses
+/// to run against the wrong chain, while a `Development` node only warns, since a local chain's
+/// id often does not match the one configured for an environment it is standing in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Environment {
+    #[default]
+    Development,
+    Production,
+}
+
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "development" | "dev" => Ok(Environment::Development),
+            "production" | "prod" => Ok(Environment::Production),
+            _ => Err(format!(
+                "unknown environment '{}', expected 'development' or 'production'",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Environment::Development => write!(f, "development"),
+            Environment::Production => write!(f, "production"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Environment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Settings for a single chain (origin or auxiliary). A config file may list per-chain settings
+/// beyond the address, such as poll interval and required confirmations.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainConfig {
+    /// Address of the chain's JSON-RPC endpoint, e.g. "http://127.0.0.1:8545".
+    pub(crate) address: String,
+    /// Expected CAIP-2 chain id (e.g. `eip155:1`), cross-checked against the live `eth_chainId`
+    /// of the node at `address` on startup. On a mismatch a `Production` node refuses to run and a
+    /// `Development` node logs a warning.
+    #[serde(default)]
+    pub(crate) chain_id: Option<ChainId>,
+    /// How often to poll the chain for new blocks, in milliseconds.
+    #[serde(default)]
+    pub(crate) poll_interval_ms: Option<u64>,
+    /// Number of blocks below the chain tip a block must be to be considered finalized.
+    #[serde(default)]
+    pub(crate) confirmations: Option<u64>,
+}
+
+/// Settings for the optional JSON-RPC status/health server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcConfig {
+    /// Whether the JSON-RPC server is enabled.
+    #[serde(default)]
+    pub(crate) enabled: bool,
+    /// Address the JSON-RPC server binds to, e.g. "127.0.0.1:3030".
+    #[serde(default = "default_rpc_bind_address")]
+    pub(crate) bind_address: String,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        RpcConfig {
+            enabled: false,
+            bind_address: default_rpc_bind_address(),
+        }
+    }
+}
+
+fn default_rpc_bind_address() -> String {
+    DEFAULT_RPC_BIND_ADDRESS.to_string()
+}
+
+/// Global config for running a mosaic node.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Settings for the origin chain.
+    pub(crate) origin: ChainConfig,
+    /// Settings for the auxiliary chain.
+    pub(crate) auxiliary: ChainConfig,
+    /// Whether the node is running in development or production mode.
+    #[serde(default)]
+    pub(crate) environment: Environment,
+    /// Settings for the optional JSON-RPC status/health server.
+    #[serde(default)]
+    pub(crate) rpc: RpcConfig,
+    /// Whether to run the node as a background daemon. Only ever set from the CLI, never from
+    /// the config file or environment.
+    #[serde(default)]
+    pub(crate) daemon: bool,
+}
+
+impl Config {
+    /// Builds the configuration from, in increasing order of precedence: built-in defaults, a
+    /// `config.yaml`/`config.toml` file, environment variables (including a `.env` file loaded
+    /// at startup), and finally CLI arguments.
+    ///
+    /// A missing default config file falls through to the defaults; a config file named with
+    /// `--config` must exist. A missing `--config` file or a malformed config file returns a
+    /// descriptive `Err`.
+    pub fn new(cli: &Cli) -> Result<Config, String> {
+        dotenv::dotenv().ok();
+
+        let mut raw = RawConfig::default();
+
+        raw.set_default("origin.address", DEFAULT_ORIGIN_ADDRESS)
+            .map_err(|err| format!("could not set default origin address: {}", err))?;
+        raw.set_default("auxiliary.address", DEFAULT_AUXILIARY_ADDRESS)
+            .map_err(|err| format!("could not set default auxiliary address: {}", err))?;
+        raw.set_default("environment", Environment::default().to_string())
+            .map_err(|err| format!("could not set default environment: {}", err))?;
+
+        let config_file = match &cli.config_path {
+            Some(path) => File::with_name(path).required(true),
+            None => File::with_name(CONFIG_FILE_STEM).required(false),
+        };
+        raw.merge(config_file)
+            .map_err(|err| format!("could not read config file: {}", err))?;
+
+        if let Ok(address) = env::var(ENV_ORIGIN_ADDRESS) {
+            raw.set("origin.address", address)
+                .map_err(|err| format!("could not apply {}: {}", ENV_ORIGIN_ADDRESS, err))?;
+        }
+        if let Ok(address) = env::var(ENV_AUXILIARY_ADDRESS) {
+            raw.set("auxiliary.address", address)
+                .map_err(|err| format!("could not apply {}: {}", ENV_AUXILIARY_ADDRESS, err))?;
+        }
+        if let Ok(mode) = env::var(ENV_MOSAIC_ENV).or_else(|_| env::var(ENV_ENVIRONMENT)) {
+            raw.set("environment", mode)
+                .map_err(|err| format!("could not apply environment override: {}", err))?;
+        }
+
+        if let Some(address) = &cli.origin {
+            raw.set("origin.address", address.clone())
+                .map_err(|err| format!("could not apply --origin: {}", err))?;
+        }
+        if let Some(address) = &cli.auxiliary {
+            raw.set("auxiliary.address", address.clone())
+                .map_err(|err| format!("could not apply --auxiliary: {}", err))?;
+        }
+        if let Some(mode) = &cli.environment {
+            raw.set("environment", mode.clone())
+                .map_err(|err| format!("could not apply --env: {}", err))?;
+        }
+        if cli.rpc {
+            raw.set("rpc.enabled", true)
+                .map_err(|err| format!("could not apply --rpc: {}", err))?;
+        }
+        if let Some(bind_address) = &cli.rpc_bind_address {
+            raw.set("rpc.bind_address", bind_address.clone())
+                .map_err(|err| format!("could not apply --rpc-bind-address: {}", err))?;
+        }
+
+        info!("Using origin chain address: {}", raw.get_str("origin.address").unwrap_or_default());
+        info!(
+            "Using auxiliary chain address: {}",
+            raw.get_str("auxiliary.address").unwrap_or_default()
+        );
+
+        let mut config: Config = raw
+            .try_into()
+            .map_err(|err| format!("could not build config: {}", err))?;
+        config.daemon = cli.daemon;
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn environment_parses_from_str_case_insensitively() {
+        assert_eq!("development".parse::<Environment>().unwrap(), Environment::Development);
+        assert_eq!("Production".parse::<Environment>().unwrap(), Environment::Production);
+        assert!("nonsense".parse::<Environment>().is_err());
+    }
+
+    // These two cases share the process-global MOSAIC_ORIGIN_ADDRESS / MOSAIC_AUXILIARY_ADDRESS
+    // env vars, which Rust's default parallel test runner would otherwise race on, so they live
+    // in a single test rather than as separate `#[test]` functions.
+    #[test]
+    fn the_config_layers_environment_variables_and_cli_arguments() {
+        env::remove_var(ENV_ORIGIN_ADDRESS);
+        env::remove_var(ENV_AUXILIARY_ADDRESS);
+
+        let cli = Cli::default();
+
+        let config = Config::new(&cli).unwrap();
+        assert_eq!(config.origin.address, DEFAULT_ORIGIN_ADDRESS.to_string());
+        assert_eq!(config.auxiliary.address, DEFAULT_AUXILIARY_ADDRESS.to_string());
+
+        env::set_var(ENV_ORIGIN_ADDRESS, "10.0.0.1");
+        let config = Config::new(&cli).unwrap();
+        assert_eq!(config.origin.address, "10.0.0.1");
+        assert_eq!(config.auxiliary.address, DEFAULT_AUXILIARY_ADDRESS.to_string());
+
+        env::set_var(ENV_AUXILIARY_ADDRESS, "10.0.0.2");
+        let config = Config::new(&cli).unwrap();
+        assert_eq!(config.origin.address, "10.0.0.1");
+        assert_eq!(config.auxiliary.address, "10.0.0.2");
+
+        let cli = Cli {
+            origin: Some("10.0.0.9".to_string()),
+            daemon: true,
+            ..Cli::default()
+        };
+        let config = Config::new(&cli).unwrap();
+        assert_eq!(config.origin.address, "10.0.0.9");
+        assert_eq!(config.auxiliary.address, "10.0.0.2");
+        assert!(config.daemon);
+
+        env::remove_var(ENV_ORIGIN_ADDRESS);
+        env::remove_var(ENV_AUXILIARY_ADDRESS);
+    }
+}
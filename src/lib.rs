@@ -21,95 +21,153 @@
 #[macro_use]
 extern crate log;
 extern crate web3;
+extern crate clap;
+extern crate config as config_rs;
+extern crate ctrlc;
+extern crate daemonize;
+extern crate dotenv;
+extern crate jsonrpc_core;
+extern crate jsonrpc_http_server;
+extern crate serde;
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
 
-use std::env;
 use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
-mod blockchain;
+use daemonize::Daemonize;
 
-// Environment variables and their defaults
-const ENV_ORIGIN_ADDRESS: &str = "MOSAIC_ORIGIN_ADDRESS";
-const ENV_AUXILIARY_ADDRESS: &str = "MOSAIC_AUXILIARY_ADDRESS";
-const DEFAULT_ORIGIN_ADDRESS: &str = "http://127.0.0.1:8545";
-const DEFAULT_AUXILIARY_ADDRESS: &str = "http://127.0.0.1:8546";
-
-/// Global config for running a mosaic node.
-pub struct Config {
-    /// Address of the origin chain, e.g. "127.0.0.1:8485"
-    origin_address: String,
-    /// Address of the auxiliary chain, e.g. "127.0.0.1:8486"
-    auxiliary_address: String,
+mod blockchain;
+mod chain_id;
+mod cli;
+mod config;
+mod rpc;
+
+use blockchain::{ChainObserver, FinalizedBlock};
+use config::ChainConfig;
+pub use chain_id::ChainId;
+pub use cli::Cli;
+pub use config::{Config, Environment};
+
+/// Path to the pidfile written when the node is started with `--daemon`.
+const PID_FILE: &str = "mosaic-node.pid";
+
+/// How often the main loop checks for a shutdown signal and newly finalized blocks while idling.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Default interval at which a chain is polled for new blocks, if not set in the config.
+const DEFAULT_POLL_INTERVAL_MS: u64 = 15_000;
+/// Default number of blocks behind the tip a block must be to be considered finalized.
+const DEFAULT_CONFIRMATIONS: u64 = 12;
+
+/// A running [`ChainObserver`]'s outputs: the channel it emits finalized blocks on, and whether
+/// its last poll succeeded.
+struct ObserverHandle {
+    blocks: Receiver<FinalizedBlock>,
+    responsive: Arc<AtomicBool>,
 }
 
-impl Config {
-    /// Reads the configuration from environment variables and creates a new Config from them. In
-    /// case an environment variable is not set, a default fallback will be used.
-    pub fn new() -> Result<Config, &'static str> {
-        // Read origin address from env and set it or fallback to default
-        let origin_address = env::var(ENV_ORIGIN_ADDRESS);
-        let origin_address = match origin_address {
-            Ok(address) => address,
-            Err(_) => {
-                info!("No origin chain address given, falling back to default.");
-                DEFAULT_ORIGIN_ADDRESS.to_string()
-            }
-        };
-
-        // Read auxiliary address from env and set it or fallback to default
-        let auxiliary_address = env::var(ENV_AUXILIARY_ADDRESS);
-        let auxiliary_address = match auxiliary_address {
-            Ok(address) => address,
-            Err(_) => {
-                info!("No auxiliary chain address given, falling back to default.");
-                DEFAULT_AUXILIARY_ADDRESS.to_string()
-            }
-        };
-
-        info!("Using origin chain address: {}", origin_address);
-        info!("Using auxiliary chain address: {}", auxiliary_address);
-
-        Ok(Config {
-            origin_address,
-            auxiliary_address,
-        })
+/// Runs a mosaic node with the given configuration.
+///
+/// Spins up a [`ChainObserver`] for the origin chain and one for the auxiliary chain, logging
+/// each newly finalized block as it is observed, then blocks in the main loop until a Ctrl-C /
+/// SIGTERM is received, at which point it stops reading from the observers and returns. The
+/// observer threads and their chain connections are not stopped; they end with the process. If
+/// `config.daemon` is set, the process forks into the background before doing any of this. If
+/// `config.rpc.enabled` is set, a JSON-RPC status/health server is also started.
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    if config.daemon {
+        info!("Daemonizing, writing pidfile to {}", PID_FILE);
+        Daemonize::new()
+            .pid_file(PID_FILE)
+            .start()
+            .map_err(|err| format!("could not daemonize: {}", err))?;
     }
-}
 
-/// Runs a mosaic node with the given configuration.
-/// Prints all accounts of the origin blockchain to std out.
-pub fn run(config: Config) -> Result<(), Box<Error>> {
-    let ethereum = blockchain::connect_to_ethereum(config.origin_address);
-    let accounts = ethereum.get_accounts();
-
-    println!("Accounts:");
-    for account in accounts {
-        println!("{}", account);
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_handler = Arc::clone(&shutdown);
+    ctrlc::set_handler(move || {
+        info!("Received shutdown signal, winding down.");
+        shutdown_handler.store(true, Ordering::SeqCst);
+    })?;
+
+    let origin = spawn_observer("origin", &config.origin, config.environment)?;
+    let auxiliary = spawn_observer("auxiliary", &config.auxiliary, config.environment)?;
+
+    let state = rpc::NodeState::new(config.environment);
+    let _rpc_server = if config.rpc.enabled {
+        let bind_address: std::net::SocketAddr = config
+            .rpc
+            .bind_address
+            .parse()
+            .map_err(|err| format!("invalid RPC bind address '{}': {}", config.rpc.bind_address, err))?;
+        info!("Starting JSON-RPC server on {}", bind_address);
+        Some(rpc::start(&bind_address, Arc::clone(&state))?)
+    } else {
+        None
+    };
+
+    info!("mosaic node is running. Press Ctrl-C to stop.");
+    while !shutdown.load(Ordering::SeqCst) {
+        for block in origin.blocks.try_iter() {
+            info!("origin chain: new finalized block {}", block.number);
+            state.set_origin_height(block.number);
+        }
+        for block in auxiliary.blocks.try_iter() {
+            info!("auxiliary chain: new finalized block {}", block.number);
+            state.set_auxiliary_height(block.number);
+        }
+        state.set_origin_responsive(origin.responsive.load(Ordering::SeqCst));
+        state.set_auxiliary_responsive(auxiliary.responsive.load(Ordering::SeqCst));
+
+        thread::sleep(SHUTDOWN_POLL_INTERVAL);
     }
 
+    info!("Shutting down mosaic node.");
+
     Ok(())
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn the_config_reads_the_environment_variables() {
-        let config = Config::new().unwrap();
-        assert_eq!(config.origin_address, DEFAULT_ORIGIN_ADDRESS.to_string());
-        assert_eq!(config.auxiliary_address, DEFAULT_AUXILIARY_ADDRESS.to_string());
+/// Connects to the chain described by `chain_config`, cross-checks its declared chain id (if
+/// any) against the live `eth_chainId`, and spawns a [`ChainObserver`] for it.
+///
+/// A mismatch refuses to start the node in [`Environment::Production`]. In
+/// [`Environment::Development`] it only logs a warning, since a local chain standing in for e.g.
+/// a production chain often does not share its chain id.
+fn spawn_observer(
+    name: &str,
+    chain_config: &ChainConfig,
+    environment: Environment,
+) -> Result<ObserverHandle, Box<dyn Error>> {
+    info!("Connecting to {} chain at {}", name, chain_config.address);
+    let node = blockchain::connect_to_ethereum(chain_config.address.clone());
+
+    if let Some(chain_id) = &chain_config.chain_id {
+        let live_reference = node.chain_id()?;
+        if live_reference != chain_id.reference {
+            let message = format!(
+                "{} chain id mismatch: config declares '{}' but node at {} reports chain id {}",
+                name, chain_id, chain_config.address, live_reference
+            );
+            if environment == Environment::Production {
+                return Err(message.into());
+            }
+            warn!("{}", message);
+        }
+    }
 
-        env::set_var(ENV_ORIGIN_ADDRESS, "10.0.0.1");
-        let config = Config::new().unwrap();
-        assert_eq!(config.origin_address, "10.0.0.1");
-        assert_eq!(config.auxiliary_address, DEFAULT_AUXILIARY_ADDRESS.to_string());
+    let poll_interval = Duration::from_millis(
+        chain_config.poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS),
+    );
+    let confirmations = chain_config.confirmations.unwrap_or(DEFAULT_CONFIRMATIONS);
+    let responsive = Arc::new(AtomicBool::new(true));
 
-        env::set_var(ENV_AUXILIARY_ADDRESS, "10.0.0.2");
-        let config = Config::new().unwrap();
-        assert_eq!(config.origin_address, "10.0.0.1");
-        assert_eq!(config.auxiliary_address, "10.0.0.2");
+    let blocks = ChainObserver::new(&node, poll_interval, confirmations, Arc::clone(&responsive)).spawn();
 
-        env::remove_var(ENV_ORIGIN_ADDRESS);
-        env::remove_var(ENV_AUXILIARY_ADDRESS);
-    }
+    Ok(ObserverHandle { blocks, responsive })
 }
@@ -0,0 +1,36 @@
+// Copyright 2018 OpenST Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Binary entry point for the mosaic node: parses CLI arguments, assembles the configuration,
+//! and runs the node.
+
+extern crate mosaic_node;
+
+use std::process;
+
+use mosaic_node::{Cli, Config};
+
+fn main() {
+    let cli = Cli::from_args();
+
+    let config = Config::new(&cli).unwrap_or_else(|err| {
+        eprintln!("could not load configuration: {}", err);
+        process::exit(1);
+    });
+
+    if let Err(err) = mosaic_node::run(config) {
+        eprintln!("mosaic node exited with an error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2018 OpenST Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON-RPC status/health endpoint.
+//!
+//! Exposes `mosaic_status` and `mosaic_health` over HTTP so a mosaic node can be inspected while
+//! it runs, mirroring the RPC control surface full Ethereum clients expose instead of only
+//! printing to std out once.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use jsonrpc_core::{IoHandler, Value};
+use jsonrpc_http_server::{Server, ServerBuilder};
+
+use config::Environment;
+
+/// Continuously-updated node state that the RPC server reports on. Shared between the main loop,
+/// which keeps it up to date, and the RPC server, which reads it on each request.
+pub struct NodeState {
+    started_at: Instant,
+    environment: Environment,
+    origin_height: AtomicU64,
+    auxiliary_height: AtomicU64,
+    last_commit_height: AtomicU64,
+    origin_responsive: AtomicBool,
+    auxiliary_responsive: AtomicBool,
+}
+
+impl NodeState {
+    pub fn new(environment: Environment) -> Arc<NodeState> {
+        Arc::new(NodeState {
+            started_at: Instant::now(),
+            environment,
+            origin_height: AtomicU64::new(0),
+            auxiliary_height: AtomicU64::new(0),
+            // No cross-chain commitment logic exists yet, so this always reports 0.
+            last_commit_height: AtomicU64::new(0),
+            origin_responsive: AtomicBool::new(true),
+            auxiliary_responsive: AtomicBool::new(true),
+        })
+    }
+
+    pub fn set_origin_height(&self, height: u64) {
+        self.origin_height.store(height, Ordering::SeqCst);
+    }
+
+    pub fn set_auxiliary_height(&self, height: u64) {
+        self.auxiliary_height.store(height, Ordering::SeqCst);
+    }
+
+    pub fn set_origin_responsive(&self, responsive: bool) {
+        self.origin_responsive.store(responsive, Ordering::SeqCst);
+    }
+
+    pub fn set_auxiliary_responsive(&self, responsive: bool) {
+        self.auxiliary_responsive.store(responsive, Ordering::SeqCst);
+    }
+}
+
+/// Starts the JSON-RPC HTTP server bound to `bind_address`, serving `mosaic_status` and
+/// `mosaic_health` from `state`. The server runs on its own background threads until the
+/// returned `Server` is dropped.
+pub fn start(bind_address: &SocketAddr, state: Arc<NodeState>) -> Result<Server, String> {
+    let mut io = IoHandler::new();
+
+    let status_state = Arc::clone(&state);
+    io.add_method("mosaic_status", move |_params| {
+        let mut status = serde_json::Map::new();
+        status.insert(
+            "origin_height".to_string(),
+            Value::from(status_state.origin_height.load(Ordering::SeqCst)),
+        );
+        status.insert(
+            "auxiliary_height".to_string(),
+            Value::from(status_state.auxiliary_height.load(Ordering::SeqCst)),
+        );
+        status.insert(
+            "last_commit_height".to_string(),
+            Value::from(status_state.last_commit_height.load(Ordering::SeqCst)),
+        );
+        status.insert(
+            "uptime_seconds".to_string(),
+            Value::from(status_state.started_at.elapsed().as_secs()),
+        );
+        status.insert(
+            "environment".to_string(),
+            Value::from(status_state.environment.to_string()),
+        );
+        Ok(Value::Object(status))
+    });
+
+    let health_state = Arc::clone(&state);
+    io.add_method("mosaic_health", move |_params| {
+        let origin_responsive = health_state.origin_responsive.load(Ordering::SeqCst);
+        let auxiliary_responsive = health_state.auxiliary_responsive.load(Ordering::SeqCst);
+
+        let mut health = serde_json::Map::new();
+        health.insert("origin_responsive".to_string(), Value::from(origin_responsive));
+        health.insert(
+            "auxiliary_responsive".to_string(),
+            Value::from(auxiliary_responsive),
+        );
+        health.insert(
+            "healthy".to_string(),
+            Value::from(origin_responsive && auxiliary_responsive),
+        );
+        Ok(Value::Object(health))
+    });
+
+    ServerBuilder::new(io)
+        .start_http(bind_address)
+        .map_err(|err| format!("could not start RPC server on {}: {}", bind_address, err))
+}